@@ -0,0 +1,152 @@
+use crate::{events::*, sequence::event::Delta};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeDivision {
+    TicksPerQuarterNote(u16),
+    SMPTE { fps: u8, ticks_per_frame: u8 },
+}
+
+impl TimeDivision {
+    /// Decodes the raw 16-bit division field from a MIDI header chunk.
+    pub fn from_raw(raw: i16) -> Self {
+        if raw < 0 {
+            let fps = (-(raw >> 8)) as u8;
+            let ticks_per_frame = (raw & 0xFF) as u8;
+            TimeDivision::SMPTE { fps, ticks_per_frame }
+        } else {
+            TimeDivision::TicksPerQuarterNote(raw as u16)
+        }
+    }
+}
+
+const DEFAULT_TEMPO: u32 = 500_000;
+
+#[derive(Debug, Clone)]
+pub struct TimedEvent {
+    pub abs_tick: u64,
+    pub abs_micros: u64,
+    pub event: Event,
+}
+
+pub struct AbsoluteTimeTracker<I: Iterator<Item = Result<Delta<u64, Event>, E>>, E> {
+    iter: I,
+    division: TimeDivision,
+    abs_tick: u64,
+    abs_micros: u64,
+    tempo: u32,
+}
+
+impl<I: Iterator<Item = Result<Delta<u64, Event>, E>>, E> AbsoluteTimeTracker<I, E> {
+    pub fn new(iter: I, division: TimeDivision) -> Self {
+        Self {
+            iter,
+            division,
+            abs_tick: 0,
+            abs_micros: 0,
+            tempo: DEFAULT_TEMPO,
+        }
+    }
+
+    fn advance(&mut self, delta_ticks: u64) {
+        self.abs_tick += delta_ticks;
+        match self.division {
+            // A PPQ of 0 is invalid, but the field comes straight from the
+            // file header, so a corrupt file can still produce one. Leave
+            // abs_micros where it is rather than dividing by zero.
+            TimeDivision::TicksPerQuarterNote(0) => {}
+            TimeDivision::TicksPerQuarterNote(ppq) => {
+                self.abs_micros += delta_ticks * self.tempo as u64 / ppq as u64;
+            }
+            TimeDivision::SMPTE { fps, ticks_per_frame } => {
+                let ticks_per_sec = fps as u64 * ticks_per_frame as u64;
+                if ticks_per_sec != 0 {
+                    self.abs_micros += delta_ticks * 1_000_000 / ticks_per_sec;
+                }
+            }
+        }
+    }
+}
+
+impl<I: Iterator<Item = Result<Delta<u64, Event>, E>>, E> Iterator for AbsoluteTimeTracker<I, E> {
+    type Item = Result<TimedEvent, E>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let next = self.iter.next()?;
+        let delta = match next {
+            Ok(delta) => delta,
+            Err(e) => return Some(Err(e)),
+        };
+
+        self.advance(delta.delta);
+
+        if let Event::Tempo(e) = &delta.event {
+            self.tempo = e.tempo;
+        }
+
+        Some(Ok(TimedEvent {
+            abs_tick: self.abs_tick,
+            abs_micros: self.abs_micros,
+            event: delta.event,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ppq_timeline_accounts_for_tempo_changes() {
+        let events: Vec<Result<Delta<u64, Event>, ()>> = vec![
+            Ok(Event::new_delta_note_on_event(480, 0, 60, 100)),
+            Ok(Event::new_delta_tempo_event(0, 1_000_000)),
+            Ok(Event::new_delta_note_off_event(480, 0, 60)),
+        ];
+
+        let mut tracker =
+            AbsoluteTimeTracker::new(events.into_iter(), TimeDivision::TicksPerQuarterNote(480));
+
+        let first = tracker.next().unwrap().unwrap();
+        assert_eq!(first.abs_tick, 480);
+        assert_eq!(first.abs_micros, 500_000);
+
+        let second = tracker.next().unwrap().unwrap();
+        assert_eq!(second.abs_tick, 480);
+        assert_eq!(second.abs_micros, 500_000);
+
+        let third = tracker.next().unwrap().unwrap();
+        assert_eq!(third.abs_tick, 960);
+        assert_eq!(third.abs_micros, 1_500_000);
+    }
+
+    #[test]
+    fn zero_ppq_division_does_not_panic() {
+        let events: Vec<Result<Delta<u64, Event>, ()>> =
+            vec![Ok(Event::new_delta_note_on_event(10, 0, 60, 100))];
+
+        let mut tracker =
+            AbsoluteTimeTracker::new(events.into_iter(), TimeDivision::TicksPerQuarterNote(0));
+
+        let first = tracker.next().unwrap().unwrap();
+        assert_eq!(first.abs_tick, 10);
+        assert_eq!(first.abs_micros, 0);
+    }
+
+    #[test]
+    fn zero_ticks_per_frame_does_not_panic() {
+        let events: Vec<Result<Delta<u64, Event>, ()>> =
+            vec![Ok(Event::new_delta_note_on_event(10, 0, 60, 100))];
+
+        let mut tracker = AbsoluteTimeTracker::new(
+            events.into_iter(),
+            TimeDivision::SMPTE {
+                fps: 30,
+                ticks_per_frame: 0,
+            },
+        );
+
+        let first = tracker.next().unwrap().unwrap();
+        assert_eq!(first.abs_tick, 10);
+        assert_eq!(first.abs_micros, 0);
+    }
+}
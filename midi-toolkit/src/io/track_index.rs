@@ -0,0 +1,154 @@
+use std::iter::Peekable;
+
+use super::{errors::MIDIParseError, readers::TrackReader, track_parser::{ParserCheckpoint, TrackParser}};
+
+struct IndexEntry {
+    checkpoint: ParserCheckpoint,
+    abs_tick: u64,
+}
+
+pub struct TrackIndex {
+    entries: Vec<IndexEntry>,
+}
+
+impl TrackIndex {
+    pub fn build<T: TrackReader>(
+        mut parser: TrackParser<T>,
+        event_interval: usize,
+    ) -> Result<Self, MIDIParseError> {
+        assert!(event_interval > 0, "event_interval must be non-zero");
+
+        let mut entries = vec![IndexEntry {
+            checkpoint: parser.checkpoint(),
+            abs_tick: 0,
+        }];
+
+        let mut abs_tick = 0u64;
+        let mut count = 0usize;
+        while let Some(next) = parser.next() {
+            let delta = next?;
+            abs_tick += delta.delta;
+            count += 1;
+            if count % event_interval == 0 {
+                entries.push(IndexEntry {
+                    checkpoint: parser.checkpoint(),
+                    abs_tick,
+                });
+            }
+        }
+
+        Ok(Self { entries })
+    }
+
+    // Finds the checkpoint nearest to, but not after, `tick`.
+    fn nearest_checkpoint(&self, tick: u64) -> &IndexEntry {
+        match self.entries.binary_search_by_key(&tick, |e| e.abs_tick) {
+            Ok(i) => &self.entries[i],
+            Err(0) => &self.entries[0],
+            Err(i) => &self.entries[i - 1],
+        }
+    }
+
+    // Reconstructs a parser at the nearest preceding checkpoint and
+    // fast-forwards it to `tick`, using `make_reader` to seek a fresh reader
+    // to the checkpoint's `reader_pos`.
+    pub fn seek_to_tick<T: TrackReader>(
+        &self,
+        tick: u64,
+        make_reader: impl FnOnce(u64) -> T,
+    ) -> Result<(Peekable<TrackParser<T>>, u64), MIDIParseError> {
+        let entry = self.nearest_checkpoint(tick);
+        let reader = make_reader(entry.checkpoint.reader_pos());
+        let mut parser = TrackParser::from_checkpoint(reader, entry.checkpoint).peekable();
+
+        let mut abs_tick = entry.abs_tick;
+        while let Some(next) = parser.peek() {
+            match next {
+                Err(_) => break,
+                Ok(delta) => {
+                    if abs_tick + delta.delta >= tick {
+                        break;
+                    }
+                    abs_tick += delta.delta;
+                    parser.next();
+                }
+            }
+        }
+
+        Ok((parser, abs_tick))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::Event;
+    use crate::io::track_writer::TrackWriter;
+
+    struct SliceReader {
+        data: Vec<u8>,
+        pos: u64,
+    }
+
+    impl TrackReader for SliceReader {
+        fn read(&mut self) -> Result<u8, MIDIParseError> {
+            let byte = *self.data.get(self.pos as usize).ok_or(MIDIParseError::CorruptEvent {
+                track_number: self.track_number(),
+                position: self.pos,
+            })?;
+            self.pos += 1;
+            Ok(byte)
+        }
+
+        fn pos(&self) -> u64 {
+            self.pos
+        }
+
+        fn track_number(&self) -> u32 {
+            0
+        }
+
+        fn is_at_end(&self) -> bool {
+            self.pos as usize >= self.data.len()
+        }
+    }
+
+    fn build_track_bytes() -> Vec<u8> {
+        let mut writer = TrackWriter::new();
+        // 5 note-on events, 100 ticks apart: absolute ticks 100, 200, 300, 400, 500.
+        for (i, delta) in [100u64, 100, 100, 100, 100].into_iter().enumerate() {
+            writer
+                .write_event(&Event::new_delta_note_on_event(delta, 0, 60 + i as u8, 100))
+                .unwrap();
+        }
+        writer.finish()
+    }
+
+    #[test]
+    fn seek_to_tick_resumes_from_nearest_checkpoint() {
+        let bytes = build_track_bytes();
+        let parser = TrackParser::new(SliceReader {
+            data: bytes.clone(),
+            pos: 0,
+        });
+        let index = TrackIndex::build(parser, 2).unwrap();
+
+        let (mut resumed, abs_tick) = index
+            .seek_to_tick(350, |pos| SliceReader {
+                data: bytes.clone(),
+                pos,
+            })
+            .unwrap();
+
+        // The nearest checkpoint at or before tick 350 is at tick 200 (after
+        // the 2nd event); fast-forwarding from there consumes the 3rd event
+        // (tick 300) and stops before the 4th (tick 400).
+        assert_eq!(abs_tick, 300);
+
+        let next = resumed.next().unwrap().unwrap();
+        match next.event {
+            Event::NoteOn(e) => assert_eq!(e.key, 63),
+            other => panic!("expected NoteOn, got {:?}", other),
+        }
+    }
+}
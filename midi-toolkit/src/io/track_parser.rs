@@ -9,6 +9,7 @@ pub struct TrackParser<T: TrackReader> {
     errored: bool,
 }
 
+#[derive(Clone, Copy)]
 pub struct ParserCheckpoint {
     pushback: i16,
     prev_command: u8,
@@ -47,6 +48,18 @@ impl<T: TrackReader> TrackParser<T> {
         }
     }
 
+    /// Captures the parser's current state so it can be reconstructed later
+    /// with [`TrackParser::from_checkpoint`], given a reader seeked back to
+    /// [`ParserCheckpoint::reader_pos`].
+    pub fn checkpoint(&self) -> ParserCheckpoint {
+        ParserCheckpoint {
+            pushback: self.pushback,
+            prev_command: self.prev_command,
+            reader_pos: self.reader.pos(),
+            ended: self.errored,
+        }
+    }
+
     fn read(&mut self) -> Result<u8, MIDIParseError> {
         if self.pushback != -1 {
             let p: u8 = self.pushback as u8;
@@ -163,7 +176,42 @@ impl<T: TrackReader> TrackParser<T> {
                         data.push(self.read_fast()?);
                     }
                     data.shrink_to_fit();
-                    ret!(Event::new_delta_system_exclusive_message_event(delta, data))
+
+                    // Strip the terminating F7 (if present) before matching
+                    // against known manufacturer signatures.
+                    let body = match data.last() {
+                        Some(0xF7) => &data[..data.len() - 1],
+                        _ => &data[..],
+                    };
+
+                    const GM_SYSTEM_ON: &[u8] = &[0x7E, 0x7F, 0x09, 0x01];
+                    const GS_RESET: &[u8] =
+                        &[0x41, 0x10, 0x42, 0x12, 0x40, 0x00, 0x7F, 0x00, 0x41];
+                    const XG_RESET: &[u8] = &[0x43, 0x10, 0x4C, 0x00, 0x00, 0x7E, 0x00];
+                    const GS_DT1_HEADER: &[u8] = &[0x41, 0x10, 0x42, 0x12];
+                    const MASTER_VOLUME_HEADER: &[u8] = &[0x7F, 0x7F, 0x04, 0x01];
+
+                    if body.starts_with(GM_SYSTEM_ON) {
+                        ret!(Event::new_delta_gm_reset_event(delta))
+                    } else if body.starts_with(GS_RESET) {
+                        ret!(Event::new_delta_gs_reset_event(delta))
+                    } else if body.starts_with(XG_RESET) {
+                        ret!(Event::new_delta_xg_reset_event(delta))
+                    } else if body.starts_with(MASTER_VOLUME_HEADER)
+                        && body.len() >= MASTER_VOLUME_HEADER.len() + 2
+                    {
+                        let ll = body[MASTER_VOLUME_HEADER.len()] as u16;
+                        let mm = body[MASTER_VOLUME_HEADER.len() + 1] as u16;
+                        ret!(Event::new_delta_master_volume_event(
+                            delta,
+                            (mm << 7) | ll
+                        ))
+                    } else if body.starts_with(GS_DT1_HEADER) {
+                        let params = body[GS_DT1_HEADER.len()..].to_vec();
+                        ret!(Event::new_delta_gs_part_parameter_event(delta, params))
+                    } else {
+                        ret!(Event::new_delta_system_exclusive_message_event(delta, data))
+                    }
                 }
                 0xF2 => {
                     let var1 = self.read()?;
@@ -298,3 +346,97 @@ impl<T: TrackReader> Iterator for TrackParser<T> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct SliceReader<'a> {
+        data: &'a [u8],
+        pos: u64,
+    }
+
+    impl<'a> TrackReader for SliceReader<'a> {
+        fn read(&mut self) -> Result<u8, MIDIParseError> {
+            let byte = *self.data.get(self.pos as usize).ok_or(MIDIParseError::CorruptEvent {
+                track_number: self.track_number(),
+                position: self.pos,
+            })?;
+            self.pos += 1;
+            Ok(byte)
+        }
+
+        fn pos(&self) -> u64 {
+            self.pos
+        }
+
+        fn track_number(&self) -> u32 {
+            0
+        }
+
+        fn is_at_end(&self) -> bool {
+            self.pos as usize >= self.data.len()
+        }
+    }
+
+    fn parse_one(sysex_body: &[u8]) -> Event {
+        let mut bytes = vec![0x00, 0xF0, sysex_body.len() as u8];
+        bytes.extend_from_slice(sysex_body);
+
+        let mut parser = TrackParser::new(SliceReader {
+            data: &bytes,
+            pos: 0,
+        });
+        parser.next().unwrap().unwrap().event
+    }
+
+    #[test]
+    fn recognizes_gm_system_on() {
+        match parse_one(&[0x7E, 0x7F, 0x09, 0x01, 0xF7]) {
+            Event::GmReset(_) => {}
+            other => panic!("expected GmReset, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn recognizes_gs_reset() {
+        match parse_one(&[0x41, 0x10, 0x42, 0x12, 0x40, 0x00, 0x7F, 0x00, 0x41, 0xF7]) {
+            Event::GsReset(_) => {}
+            other => panic!("expected GsReset, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn recognizes_xg_reset() {
+        match parse_one(&[0x43, 0x10, 0x4C, 0x00, 0x00, 0x7E, 0x00, 0xF7]) {
+            Event::XgReset(_) => {}
+            other => panic!("expected XgReset, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn recognizes_master_volume() {
+        match parse_one(&[0x7F, 0x7F, 0x04, 0x01, 0x7F, 0x7F, 0xF7]) {
+            Event::MasterVolume(e) => assert_eq!(e.volume, 16383),
+            other => panic!("expected MasterVolume, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn recognizes_gs_part_parameter_distinct_from_gs_reset() {
+        match parse_one(&[0x41, 0x10, 0x42, 0x12, 0x18, 0x01, 0x01, 0x66, 0xF7]) {
+            Event::GsPartParameter(e) => assert_eq!(e.params, vec![0x18, 0x01, 0x01, 0x66]),
+            other => panic!("expected GsPartParameter, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn falls_back_to_raw_sysex_for_unmatched_data() {
+        match parse_one(&[0x01, 0x02, 0x03, 0xF7]) {
+            Event::SystemExclusiveMessage(e) => {
+                assert_eq!(e.data, vec![0x01, 0x02, 0x03, 0xF7])
+            }
+            other => panic!("expected SystemExclusiveMessage, got {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,308 @@
+use crate::{events::*, sequence::event::Delta};
+
+use super::errors::MIDIWriteError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NoteOffPolicy {
+    Explicit,
+    VelocityZero,
+}
+
+pub struct TrackWriter {
+    buf: Vec<u8>,
+    prev_command: u8,
+    running_status: bool,
+    note_off_policy: NoteOffPolicy,
+}
+
+impl TrackWriter {
+    pub fn new() -> Self {
+        Self {
+            buf: Vec::new(),
+            prev_command: 0,
+            running_status: true,
+            note_off_policy: NoteOffPolicy::Explicit,
+        }
+    }
+
+    /// Enables or disables running-status compression (enabled by default).
+    /// Disabling it is useful when debugging.
+    pub fn set_running_status(&mut self, enabled: bool) {
+        self.running_status = enabled;
+    }
+
+    pub fn set_note_off_policy(&mut self, policy: NoteOffPolicy) {
+        self.note_off_policy = policy;
+    }
+
+    fn write_var_length(&mut self, value: u64) {
+        let mut chunks = [0u8; 10];
+        let mut len = 0;
+        let mut v = value;
+        loop {
+            chunks[len] = (v & 0x7F) as u8;
+            v >>= 7;
+            len += 1;
+            if v == 0 {
+                break;
+            }
+        }
+        for i in (0..len).rev() {
+            let byte = chunks[i];
+            if i == 0 {
+                self.buf.push(byte);
+            } else {
+                self.buf.push(byte | 0x80);
+            }
+        }
+    }
+
+    fn write_command(&mut self, command: u8) {
+        let compressible = command < 0xF0;
+        if !self.running_status || !compressible || command != self.prev_command {
+            self.buf.push(command);
+        }
+        self.prev_command = command;
+    }
+
+    fn write_meta(&mut self, kind: u8, data: &[u8]) {
+        self.buf.push(0xFF);
+        self.prev_command = 0;
+        self.buf.push(kind);
+        self.write_var_length(data.len() as u64);
+        self.buf.extend_from_slice(data);
+    }
+
+    fn write_sysex(&mut self, data: &[u8]) {
+        self.buf.push(0xF0);
+        self.prev_command = 0;
+        self.write_var_length(data.len() as u64);
+        self.buf.extend_from_slice(data);
+    }
+
+    pub fn write_event(&mut self, event: &Delta<u64, Event>) -> Result<(), MIDIWriteError> {
+        self.write_var_length(event.delta);
+        match &event.event {
+            Event::NoteOff(e) => match self.note_off_policy {
+                NoteOffPolicy::Explicit => {
+                    self.write_command(0x80 | e.channel);
+                    self.buf.extend_from_slice(&[e.key, 0]);
+                }
+                NoteOffPolicy::VelocityZero => {
+                    self.write_command(0x90 | e.channel);
+                    self.buf.extend_from_slice(&[e.key, 0]);
+                }
+            },
+            Event::NoteOn(e) if e.velocity == 0 && self.note_off_policy == NoteOffPolicy::Explicit => {
+                self.write_command(0x80 | e.channel);
+                self.buf.extend_from_slice(&[e.key, 0]);
+            }
+            Event::NoteOn(e) => {
+                self.write_command(0x90 | e.channel);
+                self.buf.extend_from_slice(&[e.key, e.velocity]);
+            }
+            Event::PolyphonicKeyPressure(e) => {
+                self.write_command(0xA0 | e.channel);
+                self.buf.extend_from_slice(&[e.key, e.velocity]);
+            }
+            Event::ControlChange(e) => {
+                self.write_command(0xB0 | e.channel);
+                self.buf.extend_from_slice(&[e.controller, e.value]);
+            }
+            Event::ProgramChange(e) => {
+                self.write_command(0xC0 | e.channel);
+                self.buf.push(e.program);
+            }
+            Event::ChannelPressure(e) => {
+                self.write_command(0xD0 | e.channel);
+                self.buf.push(e.pressure);
+            }
+            Event::PitchWheelChange(e) => {
+                self.write_command(0xE0 | e.channel);
+                let raw = (e.pitch + 8192) as u16;
+                self.buf
+                    .extend_from_slice(&[(raw & 0x7F) as u8, (raw >> 7) as u8]);
+            }
+            Event::SystemExclusiveMessage(e) => self.write_sysex(&e.data),
+            Event::GmReset(_) => self.write_sysex(&[0x7E, 0x7F, 0x09, 0x01, 0xF7]),
+            Event::GsReset(_) => self.write_sysex(&[
+                0x41, 0x10, 0x42, 0x12, 0x40, 0x00, 0x7F, 0x00, 0x41, 0xF7,
+            ]),
+            Event::XgReset(_) => {
+                self.write_sysex(&[0x43, 0x10, 0x4C, 0x00, 0x00, 0x7E, 0x00, 0xF7])
+            }
+            Event::MasterVolume(e) => {
+                let mut data = vec![0x7F, 0x7F, 0x04, 0x01];
+                data.push((e.volume & 0x7F) as u8);
+                data.push((e.volume >> 7) as u8);
+                data.push(0xF7);
+                self.write_sysex(&data);
+            }
+            Event::GsPartParameter(e) => {
+                let mut data = vec![0x41, 0x10, 0x42, 0x12];
+                data.extend_from_slice(&e.params);
+                data.push(0xF7);
+                self.write_sysex(&data);
+            }
+            Event::SongPositionPointer(e) => {
+                self.write_command(0xF2);
+                self.buf
+                    .extend_from_slice(&[(e.position & 0x7F) as u8, (e.position >> 7) as u8]);
+            }
+            Event::SongSelect(e) => {
+                self.write_command(0xF3);
+                self.buf.push(e.position);
+            }
+            Event::TuneRequest(_) => self.write_command(0xF6),
+            Event::EndOfExclusive(_) => self.write_command(0xF7),
+            Event::TrackStart(_) => self.write_meta(0x00, &[]),
+            Event::Text(e) => self.write_meta(e.kind.as_val(), &e.data),
+            Event::ChannelPrefix(e) => self.write_meta(0x20, &[e.prefix]),
+            Event::MidiPort(e) => self.write_meta(0x21, &[e.port]),
+            Event::Tempo(e) => {
+                let t = e.tempo.to_be_bytes();
+                self.write_meta(0x51, &t[1..])
+            }
+            Event::SMPTEOffset(e) => {
+                self.write_meta(0x54, &[e.hr, e.mn, e.se, e.fr, e.ff])
+            }
+            Event::TimeSignature(e) => self.write_meta(0x58, &[e.nn, e.dd, e.cc, e.bb]),
+            Event::KeySignature(e) => self.write_meta(0x59, &[e.sf, e.mi]),
+            Event::UnknownMeta(e) => self.write_meta(e.command, &e.data),
+            Event::Undefined(e) => self.write_command(e.command),
+        }
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> Vec<u8> {
+        self.write_var_length(0);
+        self.write_meta(0x2F, &[]);
+        self.buf
+    }
+}
+
+impl Default for TrackWriter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::{errors::MIDIParseError, readers::TrackReader, track_parser::TrackParser};
+
+    struct SliceReader<'a> {
+        data: &'a [u8],
+        pos: u64,
+    }
+
+    impl<'a> TrackReader for SliceReader<'a> {
+        fn read(&mut self) -> Result<u8, MIDIParseError> {
+            let byte = *self.data.get(self.pos as usize).ok_or(MIDIParseError::CorruptEvent {
+                track_number: self.track_number(),
+                position: self.pos,
+            })?;
+            self.pos += 1;
+            Ok(byte)
+        }
+
+        fn pos(&self) -> u64 {
+            self.pos
+        }
+
+        fn track_number(&self) -> u32 {
+            0
+        }
+
+        fn is_at_end(&self) -> bool {
+            self.pos as usize >= self.data.len()
+        }
+    }
+
+    #[test]
+    fn round_trips_note_events_through_running_status() {
+        let mut writer = TrackWriter::new();
+        writer
+            .write_event(&Event::new_delta_note_on_event(0, 0, 60, 100))
+            .unwrap();
+        writer
+            .write_event(&Event::new_delta_note_on_event(4, 0, 64, 90))
+            .unwrap();
+        writer
+            .write_event(&Event::new_delta_note_off_event(4, 0, 60))
+            .unwrap();
+
+        let bytes = writer.finish();
+
+        let parser = TrackParser::new(SliceReader {
+            data: &bytes,
+            pos: 0,
+        });
+        let parsed: Vec<_> = parser.collect::<Result<Vec<_>, _>>().unwrap();
+
+        assert_eq!(parsed.len(), 3);
+        match &parsed[0].event {
+            Event::NoteOn(e) => {
+                assert_eq!(e.channel, 0);
+                assert_eq!(e.key, 60);
+                assert_eq!(e.velocity, 100);
+            }
+            other => panic!("expected NoteOn, got {:?}", other),
+        }
+        match &parsed[1].event {
+            Event::NoteOn(e) => {
+                assert_eq!(e.key, 64);
+                assert_eq!(e.velocity, 90);
+            }
+            other => panic!("expected NoteOn, got {:?}", other),
+        }
+        match &parsed[2].event {
+            Event::NoteOff(e) => assert_eq!(e.key, 60),
+            other => panic!("expected NoteOff, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn explicit_policy_rewrites_zero_velocity_note_on_as_note_off() {
+        let mut writer = TrackWriter::new();
+        writer.set_note_off_policy(NoteOffPolicy::Explicit);
+        writer
+            .write_event(&Event::new_delta_note_on_event(0, 0, 60, 0))
+            .unwrap();
+
+        let bytes = writer.finish();
+        assert_eq!(&bytes[0..4], &[0x00, 0x80, 60, 0]);
+    }
+
+    #[test]
+    fn velocity_zero_policy_keeps_zero_velocity_note_on_as_is() {
+        let mut writer = TrackWriter::new();
+        writer.set_note_off_policy(NoteOffPolicy::VelocityZero);
+        writer
+            .write_event(&Event::new_delta_note_on_event(0, 0, 60, 0))
+            .unwrap();
+
+        let bytes = writer.finish();
+        assert_eq!(&bytes[0..4], &[0x00, 0x90, 60, 0]);
+    }
+
+    #[test]
+    fn running_status_omits_repeated_command_byte() {
+        let mut writer = TrackWriter::new();
+        writer
+            .write_event(&Event::new_delta_note_on_event(0, 0, 60, 100))
+            .unwrap();
+        writer
+            .write_event(&Event::new_delta_note_on_event(4, 0, 64, 90))
+            .unwrap();
+
+        let bytes = writer.finish();
+
+        // First note-on: delta 0x00, status 0x90, key 60, velocity 100.
+        assert_eq!(&bytes[0..4], &[0x00, 0x90, 60, 100]);
+        // Second note-on shares status with the first, so the 0x90 byte is
+        // omitted by running-status compression.
+        assert_eq!(&bytes[4..7], &[0x04, 64, 90]);
+    }
+}
@@ -0,0 +1,133 @@
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::BinaryHeap,
+};
+
+use gen_iter::GenIter;
+
+use crate::{events::Event, sequence::event::Delta, yield_error};
+
+/// Merge an array of delta-tick event iterators (e.g. several `TrackParser`s)
+/// into a single delta-ordered stream, suitable for writing out as a Format 0
+/// track with `TrackWriter`.
+pub fn merge_events_array<Err, I: Iterator<Item = Result<Delta<u64, Event>, Err>> + Sized>(
+    array: Vec<I>,
+) -> impl Iterator<Item = Result<Delta<u64, Event>, Err>> {
+    struct SeqTime<Err, I: Iterator<Item = Result<Delta<u64, Event>, Err>> + Sized> {
+        iter: I,
+        // The track's original index, used to keep events on the same tick
+        // in a stable, predictable order.
+        index: usize,
+        abs_tick: u64,
+        next: Event,
+    }
+
+    impl<Err, I: Iterator<Item = Result<Delta<u64, Event>, Err>> + Sized> PartialEq
+        for SeqTime<Err, I>
+    {
+        fn eq(&self, other: &Self) -> bool {
+            (self.abs_tick, self.index) == (other.abs_tick, other.index)
+        }
+    }
+
+    impl<Err, I: Iterator<Item = Result<Delta<u64, Event>, Err>> + Sized> Eq for SeqTime<Err, I> {}
+
+    impl<Err, I: Iterator<Item = Result<Delta<u64, Event>, Err>> + Sized> PartialOrd
+        for SeqTime<Err, I>
+    {
+        fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+
+    impl<Err, I: Iterator<Item = Result<Delta<u64, Event>, Err>> + Sized> Ord for SeqTime<Err, I> {
+        fn cmp(&self, other: &Self) -> Ordering {
+            (self.abs_tick, self.index).cmp(&(other.abs_tick, other.index))
+        }
+    }
+
+    GenIter(move || {
+        let mut heap = BinaryHeap::new();
+        for (index, mut seq) in array.into_iter().enumerate() {
+            match seq.next() {
+                None => continue,
+                Some(Err(e)) => yield_error!(Err(e)),
+                Some(Ok(delta)) => {
+                    heap.push(Reverse(SeqTime {
+                        abs_tick: delta.delta,
+                        index,
+                        next: delta.event,
+                        iter: seq,
+                    }));
+                }
+            }
+        }
+
+        let mut prev_tick = 0u64;
+        while let Some(Reverse(mut smallest)) = heap.pop() {
+            let delta_ticks = smallest.abs_tick - prev_tick;
+            prev_tick = smallest.abs_tick;
+
+            let event = smallest.next;
+            yield Ok(Delta {
+                delta: delta_ticks,
+                event,
+            });
+
+            match smallest.iter.next() {
+                None => continue,
+                Some(Err(e)) => yield_error!(Err(e)),
+                Some(Ok(delta)) => {
+                    smallest.abs_tick += delta.delta;
+                    smallest.next = delta.event;
+                    heap.push(Reverse(smallest));
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merges_by_absolute_tick_and_rebuilds_deltas() {
+        let track0 = vec![
+            Ok(Event::new_delta_note_on_event(100, 0, 60, 100)),
+            Ok(Event::new_delta_note_on_event(100, 0, 61, 100)),
+        ];
+        // Ties on absolute tick 200 with track0's second event.
+        let track1 = vec![Ok(Event::new_delta_note_on_event(200, 1, 70, 100))];
+
+        let merged: Vec<Delta<u64, Event>> =
+            merge_events_array::<(), _>(vec![track0.into_iter(), track1.into_iter()])
+                .collect::<Result<Vec<_>, ()>>()
+                .unwrap();
+
+        assert_eq!(merged.len(), 3);
+
+        assert_eq!(merged[0].delta, 100);
+        match &merged[0].event {
+            Event::NoteOn(e) => assert_eq!(e.key, 60),
+            other => panic!("expected NoteOn, got {:?}", other),
+        }
+
+        assert_eq!(merged[1].delta, 100);
+        match &merged[1].event {
+            Event::NoteOn(e) => assert_eq!(e.key, 61),
+            other => panic!("expected NoteOn, got {:?}", other),
+        }
+
+        // track1's event has a later sequence index, so it breaks the tie
+        // by coming after track0's, with a zero re-emitted delta.
+        assert_eq!(merged[2].delta, 0);
+        match &merged[2].event {
+            Event::NoteOn(e) => assert_eq!(e.key, 70),
+            other => panic!("expected NoteOn, got {:?}", other),
+        }
+
+        let total: u64 = merged.iter().map(|d| d.delta).sum();
+        assert_eq!(total, 200);
+    }
+}
@@ -1,3 +1,8 @@
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::BinaryHeap,
+};
+
 use gen_iter::GenIter;
 
 use crate::{notes::MIDINote, num::MIDINum, unwrap, yield_error};
@@ -13,13 +18,45 @@ pub fn merge_notes_array<
 ) -> impl Iterator<Item = Result<N, Err>> {
     struct SeqTime<T: MIDINum, N: MIDINote<T>, Err, I: Iterator<Item = Result<N, Err>> + Sized> {
         iter: I,
+        // The sequence's original index, used to keep the merge order
+        // between sequences deterministic when their times tie.
+        index: usize,
         time: T,
-        next: Option<N>,
+        next: N,
+    }
+
+    impl<T: MIDINum, N: MIDINote<T>, Err, I: Iterator<Item = Result<N, Err>> + Sized> PartialEq
+        for SeqTime<T, N, Err, I>
+    {
+        fn eq(&self, other: &Self) -> bool {
+            (self.time, self.index) == (other.time, other.index)
+        }
+    }
+
+    impl<T: MIDINum, N: MIDINote<T>, Err, I: Iterator<Item = Result<N, Err>> + Sized> Eq
+        for SeqTime<T, N, Err, I>
+    {
+    }
+
+    impl<T: MIDINum, N: MIDINote<T>, Err, I: Iterator<Item = Result<N, Err>> + Sized> PartialOrd
+        for SeqTime<T, N, Err, I>
+    {
+        fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+
+    impl<T: MIDINum, N: MIDINote<T>, Err, I: Iterator<Item = Result<N, Err>> + Sized> Ord
+        for SeqTime<T, N, Err, I>
+    {
+        fn cmp(&self, other: &Self) -> Ordering {
+            (self.time, self.index).cmp(&(other.time, other.index))
+        }
     }
 
     GenIter(move || {
-        let mut seqences = Vec::new();
-        for mut seq in array.into_iter() {
+        let mut heap = BinaryHeap::new();
+        for (index, mut seq) in array.into_iter().enumerate() {
             let first = seq.next();
             match first {
                 None => continue,
@@ -28,51 +65,66 @@ pub fn merge_notes_array<
                     Ok(e) => {
                         let s = SeqTime {
                             time: e.start(),
-                            next: Some(e),
+                            index,
+                            next: e,
                             iter: seq,
                         };
-                        seqences.push(s);
+                        heap.push(Reverse(s));
                     }
                 },
             }
         }
 
-        while seqences.len() > 0 {
-            let len = seqences.len();
-            let mut smallest_index = 0;
-            let mut smallest_time = seqences[0].time;
-            for i in 0..len {
-                let next = &seqences[i];
-                if next.time < smallest_time {
-                    smallest_time = next.time;
-                    smallest_index = i;
+        while let Some(Reverse(mut smallest)) = heap.pop() {
+            let note = smallest.next;
+            let next = smallest.iter.next();
+            yield Ok(note);
+            match next {
+                None => continue,
+                Some(next) => {
+                    let next = unwrap!(next);
+                    smallest.time = next.start();
+                    smallest.next = next;
+                    heap.push(Reverse(smallest));
                 }
             }
-            loop {
-                let (note, next) = {
-                    let smallest = &mut seqences[smallest_index];
+        }
+    })
+}
 
-                    let note = smallest.next.take().unwrap();
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-                    (note, smallest.iter.next())
-                };
-                yield Ok(note);
-                match next {
-                    None => {
-                        seqences.remove(smallest_index);
-                        break;
-                    }
-                    Some(next) => {
-                        let next = unwrap!(next);
-                        let mut smallest = &mut seqences[smallest_index];
-                        smallest.time = next.start();
-                        smallest.next = Some(next);
-                    }
-                }
-                if seqences[smallest_index].time != smallest_time {
-                    break;
-                }
-            }
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    struct TestNote {
+        start: u64,
+        seq: usize,
+    }
+
+    impl MIDINote<u64> for TestNote {
+        fn start(&self) -> u64 {
+            self.start
         }
-    })
+    }
+
+    #[test]
+    fn ties_break_on_sequence_index() {
+        // Both sequences have a note starting at tick 10; sequence 0 must
+        // be emitted before sequence 1 regardless of heap pop order.
+        let seq0 = vec![
+            Ok(TestNote { start: 10, seq: 0 }),
+            Ok(TestNote { start: 20, seq: 0 }),
+        ];
+        let seq1 = vec![Ok(TestNote { start: 10, seq: 1 })];
+
+        let merged: Vec<TestNote> =
+            merge_notes_array::<u64, TestNote, (), _>(vec![seq0.into_iter(), seq1.into_iter()])
+                .collect::<Result<Vec<_>, ()>>()
+                .unwrap();
+
+        assert_eq!(merged[0], TestNote { start: 10, seq: 0 });
+        assert_eq!(merged[1], TestNote { start: 10, seq: 1 });
+        assert_eq!(merged[2], TestNote { start: 20, seq: 0 });
+    }
 }